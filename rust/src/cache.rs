@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use crate::models::Series;
+
+/// Identifies a single offset window's fetched series so repeated requests
+/// for the same (query, offset, time range) can be served from memory
+/// instead of re-hitting the upstream.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub query: String,
+    pub offset: i64,
+    pub time: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub step: Option<String>,
+}
+
+struct CacheEntry {
+    series: Vec<Series>,
+    inserted_at: Instant,
+    ttl: Duration,
+}
+
+impl CacheEntry {
+    fn is_expired(&self) -> bool {
+        self.inserted_at.elapsed() >= self.ttl
+    }
+}
+
+/// In-memory TTL cache for fetched offset windows. Historical offsets
+/// (7/14/21/28 days ago) are effectively immutable once in the past, so they
+/// can be cached far longer than the `current` window.
+#[derive(Clone, Default)]
+pub struct WindowCache {
+    entries: Arc<Mutex<HashMap<CacheKey, CacheEntry>>>,
+}
+
+impl WindowCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<Vec<Series>> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some(entry) if !entry.is_expired() => Some(entry.series.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn put(&self, key: CacheKey, series: Vec<Series>, ttl: Duration) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(key, CacheEntry {
+            series,
+            inserted_at: Instant::now(),
+            ttl,
+        });
+    }
+}
@@ -8,6 +8,53 @@ pub struct Config {
     pub listen_port: u16,
     pub timeframes: Vec<String>,
     pub offsets: Vec<i64>,
+    /// How long the `current` (offset 0) window stays cached, in seconds.
+    pub cache_ttl_current_secs: u64,
+    /// How long historical (offset > 0) windows stay cached, in seconds.
+    /// These are effectively immutable once in the past, so this is set
+    /// much higher than `cache_ttl_current_secs`.
+    pub cache_ttl_historical_secs: u64,
+    /// Path to a PEM certificate chain used to terminate TLS on the proxy's
+    /// own listener. Requires `tls_key_path` to also be set.
+    pub tls_cert_path: Option<String>,
+    /// Path to the PEM private key matching `tls_cert_path`.
+    pub tls_key_path: Option<String>,
+    /// Whether the upstream Prometheus connection should be made over TLS.
+    pub upstream_tls: bool,
+    /// Which upstream backend to use: `"prometheus"` talks directly to
+    /// `upstream_url`; `"grafana"` routes through a Grafana datasource
+    /// proxy using `grafana_base_url`/`grafana_datasource_id`/
+    /// `grafana_api_token` instead.
+    pub backend: String,
+    /// Base URL of the Grafana instance. Required when `backend = "grafana"`.
+    pub grafana_base_url: Option<String>,
+    /// ID of the Grafana-managed Prometheus datasource to proxy through.
+    /// Required when `backend = "grafana"`.
+    pub grafana_datasource_id: Option<u64>,
+    /// Bearer API token used to authenticate with Grafana's datasource
+    /// proxy. Required when `backend = "grafana"`.
+    pub grafana_api_token: Option<String>,
+    /// The `k` multiplier used by the `zScoreAgainstLast28` command's
+    /// upper/lower anomaly bands: `mean ± k * stddev`.
+    pub zscore_band_multiplier: f64,
+    /// Connect/request timeout applied to every upstream HTTP call, in
+    /// milliseconds.
+    pub request_timeout_ms: u64,
+    /// How many times a transient upstream failure is retried before the
+    /// window is given up on, not counting the initial attempt.
+    pub max_retries: u32,
+    /// Base delay for the retry loop's exponential backoff, in
+    /// milliseconds. Attempt `n` waits `backoff_base_ms * 2^n`.
+    pub backoff_base_ms: u64,
+    /// Largest number of points `fetch_windows_range` will request from a
+    /// single `query_range` call before splitting the interval into
+    /// consecutive sub-windows, to stay under Prometheus's per-series
+    /// resolution limit.
+    pub max_points: u64,
+    /// How many offset window fetches run concurrently at once. The
+    /// sub-windows of a chunked range fetch are fetched one after another
+    /// within their window.
+    pub max_concurrent_fetches: usize,
 }
 
 impl Default for Config {
@@ -30,6 +77,21 @@ impl Default for Config {
                 21 * 24 * 3600,
                 28 * 24 * 3600,
             ],
+            cache_ttl_current_secs: 30,
+            cache_ttl_historical_secs: 3600,
+            tls_cert_path: None,
+            tls_key_path: None,
+            upstream_tls: false,
+            backend: "prometheus".to_string(),
+            grafana_base_url: None,
+            grafana_datasource_id: None,
+            grafana_api_token: None,
+            zscore_band_multiplier: 2.0,
+            request_timeout_ms: 10_000,
+            max_retries: 2,
+            backoff_base_ms: 200,
+            max_points: 11_000,
+            max_concurrent_fetches: 8,
         }
     }
 }
@@ -9,20 +9,41 @@ use std::fmt;
 #[derive(Debug)]
 pub enum AppError {
     Upstream(reqwest::Error),
+    /// An upstream backend responded, but its payload couldn't be used
+    /// (e.g. `status: "error"`, or a result shape the backend doesn't
+    /// support parsing).
+    Backend(String),
     Internal(String),
     InvalidTimeframe(String),
+    /// A transient upstream failure persisted past `Config::max_retries`.
+    RetriesExhausted(String),
+}
+
+impl AppError {
+    /// Whether this error is worth retrying, i.e. it looks like a
+    /// transport-level hiccup rather than a permanent rejection.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            AppError::Upstream(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             AppError::Upstream(e) => write!(f, "Upstream error: {}", e),
+            AppError::Backend(e) => write!(f, "Backend error: {}", e),
             AppError::Internal(e) => write!(f, "Internal error: {}", e),
             AppError::InvalidTimeframe(tf) => write!(f, "Invalid timeframe: {}", tf),
+            AppError::RetriesExhausted(e) => write!(f, "Retries exhausted: {}", e),
         }
     }
 }
 
+impl std::error::Error for AppError {}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
@@ -30,6 +51,10 @@ impl IntoResponse for AppError {
                 StatusCode::BAD_GATEWAY,
                 format!("Upstream error: {}", err),
             ),
+            AppError::Backend(err) => (
+                StatusCode::BAD_GATEWAY,
+                format!("Backend error: {}", err),
+            ),
             AppError::Internal(err) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Internal error: {}", err),
@@ -38,6 +63,10 @@ impl IntoResponse for AppError {
                 StatusCode::BAD_REQUEST,
                 format!("Invalid timeframe: {}", err),
             ),
+            AppError::RetriesExhausted(err) => (
+                StatusCode::GATEWAY_TIMEOUT,
+                format!("Retries exhausted: {}", err),
+            ),
         };
 
         (status, Json(json!({
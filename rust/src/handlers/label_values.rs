@@ -5,7 +5,6 @@ use axum::{
 use serde_json::json;
 use log::debug;
 use crate::proxy::ChronoProxy;
-use reqwest::Client;
 
 pub async fn label_values_handler(
     State(proxy): State<ChronoProxy>,
@@ -21,20 +20,11 @@ pub async fn label_values_handler(
         }));
     }
 
-    let client = Client::new();
-    let url = format!("http://localhost:9090/api/v1/label/{}/values", label);
-
-    match client.get(&url).send().await {
-        Ok(response) => {
-            match response.json::<serde_json::Value>().await {
-                Ok(data) => Json(data),
-                Err(e) => Json(json!({
-                    "status": "error",
-                    "errorType": "execution",
-                    "error": e.to_string()
-                }))
-            }
-        }
+    match proxy.upstream.label_values(&label).await {
+        Ok(values) => Json(json!({
+            "status": "success",
+            "data": values
+        })),
         Err(e) => Json(json!({
             "status": "error",
             "errorType": "execution",
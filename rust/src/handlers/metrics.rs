@@ -0,0 +1,9 @@
+use axum::{extract::State, response::IntoResponse};
+use crate::proxy::ChronoProxy;
+
+pub async fn metrics_handler(State(proxy): State<ChronoProxy>) -> impl IntoResponse {
+    match &proxy.metrics_handle {
+        Some(handle) => handle.render(),
+        None => String::new(),
+    }
+}
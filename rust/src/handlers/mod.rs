@@ -2,8 +2,12 @@ mod query;
 mod query_range;
 mod labels;
 mod label_values;
+mod metrics;
+#[cfg(test)]
+mod tests;
 
 pub use query::query_handler;
 pub use query_range::query_range_handler;
 pub use labels::labels_handler;
-pub use label_values::label_values_handler;
\ No newline at end of file
+pub use label_values::label_values_handler;
+pub use metrics::metrics_handler;
\ No newline at end of file
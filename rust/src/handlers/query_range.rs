@@ -13,6 +13,7 @@ use crate::utils::{
     append_with_command,
     append_compare,
     append_percent,
+    append_zscore,
     filter_by_timeframe,
     dedupe_series,
 };
@@ -53,17 +54,25 @@ pub async fn query_range_handler(
     };
 
     let average_series = build_last_month_average(&current_series, true);
-    
+
     // Create clones for index_by_signature
     let current_for_index = current_series.clone();
     let average_for_index = average_series.clone();
-    
+    let all_windows = current_series.clone();
+
     let (current_map, avg_map) = index_by_signature(&current_for_index, &average_for_index);
 
     let mut final_result = current_series;
     final_result = append_with_command(final_result, average_series, &command);
     final_result = append_compare(final_result, &current_map, &avg_map, &command, true);
     final_result = append_percent(final_result, &current_map, &avg_map, &command, true);
+    final_result = append_zscore(
+        final_result,
+        &all_windows,
+        &command,
+        true,
+        proxy.zscore_band_multiplier,
+    );
 
     if !timeframe.is_empty() {
         final_result = filter_by_timeframe(final_result, &timeframe);
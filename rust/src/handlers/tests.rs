@@ -1,14 +1,48 @@
 #[cfg(test)]
 mod tests {
-    use super::*;
-    use axum::http::Request;
+    use async_trait::async_trait;
+    use axum::{body::Body, http::Request, http::StatusCode, routing::get, Router};
+    use std::collections::HashMap;
+    use std::sync::Arc;
     use tower::ServiceExt;
 
+    use crate::error::AppError;
+    use crate::models::{Sample, Series};
+    use crate::proxy::ChronoProxy;
+    use crate::upstream::Upstream;
+
+    struct MockUpstream;
+
+    #[async_trait]
+    impl Upstream for MockUpstream {
+        async fn instant_query(&self, _params: &HashMap<String, Vec<String>>) -> Result<Vec<Series>, AppError> {
+            let mut metric = HashMap::new();
+            metric.insert("__name__".to_string(), "up".to_string());
+            Ok(vec![Series {
+                metric,
+                value: Some(Sample { timestamp: 1_700_000_000.0, value: 1.0 }),
+                values: None,
+            }])
+        }
+
+        async fn range_query(&self, _params: &HashMap<String, Vec<String>>) -> Result<Vec<Series>, AppError> {
+            Ok(Vec::new())
+        }
+
+        async fn labels(&self) -> Result<Vec<String>, AppError> {
+            Ok(vec!["__name__".to_string()])
+        }
+
+        async fn label_values(&self, _label: &str) -> Result<Vec<String>, AppError> {
+            Ok(Vec::new())
+        }
+    }
+
     #[tokio::test]
     async fn test_query_handler() {
-        let proxy = ChronoProxy::new();
+        let proxy = ChronoProxy::new().with_upstream(Arc::new(MockUpstream));
         let app = Router::new()
-            .route("/api/v1/query", get(super::query_handler))
+            .route("/api/v1/query", get(super::super::query_handler))
             .with_state(proxy);
 
         let response = app
@@ -23,4 +57,4 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::OK);
     }
-}
\ No newline at end of file
+}
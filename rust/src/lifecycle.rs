@@ -0,0 +1,46 @@
+use log::{debug, info};
+
+/// Notifies systemd (via `NOTIFY_SOCKET`) that the proxy is ready to serve
+/// traffic. A no-op outside of a systemd unit.
+pub fn notify_ready() {
+    if let Err(err) = sd_notify::notify(false, &[sd_notify::NotifyState::Ready]) {
+        debug!("sd_notify READY failed (not running under systemd?): {}", err);
+    }
+}
+
+/// Notifies systemd that the proxy is shutting down. A no-op outside of a
+/// systemd unit.
+pub fn notify_stopping() {
+    if let Err(err) = sd_notify::notify(false, &[sd_notify::NotifyState::Stopping]) {
+        debug!("sd_notify STOPPING failed (not running under systemd?): {}", err);
+    }
+}
+
+/// Resolves once SIGINT or SIGTERM is received, for use with
+/// `with_graceful_shutdown`/`Handle::graceful_shutdown`.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    info!("shutdown signal received, starting graceful shutdown");
+    notify_stopping();
+}
@@ -3,15 +3,26 @@ use axum::{
     routing::{get, post},
     middleware::from_fn,
 };
+use axum_server::tls_rustls::RustlsConfig;
 use clap::Parser;
 use log::{info, LevelFilter};
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 mod handlers;
 mod proxy;
 mod utils;
 mod models;
 mod middleware;
+mod metrics;
+mod cache;
+mod tls;
+mod config;
+mod lifecycle;
+mod upstream;
+mod error;
+mod resilience;
 
 #[derive(Parser, Debug)]
 #[clap(about = "Chronotheus - A Prometheus Historical Data Proxy")]
@@ -19,30 +30,120 @@ struct Args {
     #[clap(short, long, default_value = "false")]
     debug: bool,
 
-    #[clap(short, long, default_value = "8080")]
-    port: u16,
+    /// Overrides `listen_port` from the config file.
+    #[clap(short, long)]
+    port: Option<u16>,
+
+    /// Path to a PEM certificate chain; serves HTTPS when set together with `--tls-key`.
+    /// Overrides `tls_cert_path` from the config file.
+    #[clap(long)]
+    tls_cert: Option<PathBuf>,
+
+    /// Path to the PEM private key matching `--tls-cert`.
+    /// Overrides `tls_key_path` from the config file.
+    #[clap(long)]
+    tls_key: Option<PathBuf>,
+
+    /// Connect to the upstream Prometheus over TLS using the OS native root store.
+    #[clap(long, default_value = "false")]
+    upstream_tls: bool,
 }
 
 #[tokio::main]
 async fn main() {
     let args = Args::parse();
-    
+
     env_logger::builder()
         .filter_level(if args.debug { LevelFilter::Debug } else { LevelFilter::Info })
         .init();
 
-    let proxy = proxy::ChronoProxy::new();
+    let config = config::Config::load();
+
+    let metrics_handle = crate::metrics::install_recorder();
+    let upstream_tls = args.upstream_tls || config.upstream_tls;
+    let http_client = crate::tls::build_http_client(upstream_tls, config.request_timeout_ms);
+    let upstream: Arc<dyn upstream::Upstream> = match config.backend.as_str() {
+        "grafana" => {
+            let grafana_base_url = config
+                .grafana_base_url
+                .clone()
+                .expect("grafana_base_url is required when backend = \"grafana\"");
+            let datasource_id = config
+                .grafana_datasource_id
+                .expect("grafana_datasource_id is required when backend = \"grafana\"");
+            let api_token = config
+                .grafana_api_token
+                .clone()
+                .expect("grafana_api_token is required when backend = \"grafana\"");
+            Arc::new(upstream::GrafanaProxyUpstream::new(
+                http_client,
+                grafana_base_url,
+                datasource_id,
+                api_token,
+            ))
+        }
+        _ => Arc::new(upstream::PrometheusHttpUpstream::new(
+            http_client,
+            config.upstream_url.clone(),
+        )),
+    };
+    let proxy = proxy::ChronoProxy::from_config(&config)
+        .with_metrics_handle(metrics_handle)
+        .with_upstream(upstream);
 
     let app = Router::new()
         .route("/api/v1/query", get(handlers::query_handler).post(handlers::query_handler))
         .route("/api/v1/query_range", get(handlers::query_range_handler))
         .route("/api/v1/labels", get(handlers::labels_handler))
         .route("/api/v1/label/:label/values", get(handlers::label_values_handler))
+        .route("/metrics", get(handlers::metrics_handler))
         .layer(from_fn(crate::middleware::logging))
         .with_state(proxy);
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], args.port));
-    info!("🚀 Chronotheus proxy listening on {}", addr);
+    let listen_ip: std::net::IpAddr = config
+        .listen_addr
+        .parse()
+        .expect("invalid listen_addr in config");
+    let port = args.port.unwrap_or(config.listen_port);
+    let addr = SocketAddr::new(listen_ip, port);
+
+    let tls_cert = args.tls_cert.clone().or_else(|| config.tls_cert_path.clone().map(PathBuf::from));
+    let tls_key = args.tls_key.clone().or_else(|| config.tls_key_path.clone().map(PathBuf::from));
+
+    match (tls_cert, tls_key) {
+        (Some(cert), Some(key)) => {
+            let tls_config = RustlsConfig::from_pem_file(cert, key)
+                .await
+                .expect("failed to load TLS certificate/key");
+
+            let handle = axum_server::Handle::new();
+            let shutdown_handle = handle.clone();
+            tokio::spawn(async move {
+                crate::lifecycle::shutdown_signal().await;
+                shutdown_handle.graceful_shutdown(None);
+            });
+
+            // `bind_rustls` only binds the socket lazily when the
+            // returned server future is polled, so bind explicitly first
+            // and only notify systemd once the port is actually listening.
+            let listener = std::net::TcpListener::bind(addr).unwrap();
 
-    axum::serve(tokio::net::TcpListener::bind(addr).await.unwrap(), app).await.unwrap();
+            info!("🔒 Chronotheus proxy listening on {} (TLS)", addr);
+            crate::lifecycle::notify_ready();
+            axum_server::from_tcp_rustls(listener, tls_config)
+                .handle(handle)
+                .serve(app.into_make_service())
+                .await
+                .unwrap();
+        }
+        _ => {
+            let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+            info!("🚀 Chronotheus proxy listening on {}", addr);
+            crate::lifecycle::notify_ready();
+            axum::serve(listener, app)
+                .with_graceful_shutdown(crate::lifecycle::shutdown_signal())
+                .await
+                .unwrap();
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,10 @@
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+
+/// Installs the process-wide Prometheus recorder used by the `logging`
+/// middleware and the upstream fetch instrumentation, and returns the
+/// handle used to render `/metrics`.
+pub fn install_recorder() -> PrometheusHandle {
+    PrometheusBuilder::new()
+        .install_recorder()
+        .expect("failed to install Prometheus metrics recorder")
+}
@@ -1,23 +1,53 @@
 use axum::{
     body::Body,
+    extract::MatchedPath,
     middleware::Next,
     response::Response,
     http::Request,
 };
 use std::time::Instant;
 use log::info;
+use metrics::{counter, gauge, histogram};
 
 pub async fn logging(
     req: Request<Body>,
     next: Next,
 ) -> Response {
     let start = Instant::now();
-    let path = req.uri().path().to_owned();
+    // Use the matched route template, not the raw URI, so that
+    // user-supplied path segments (e.g. the label name in
+    // `/api/v1/label/:label/values`) don't become unbounded-cardinality
+    // metric label values. Requests that match no route fall back to the
+    // raw URI path.
+    let path = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_owned())
+        .unwrap_or_else(|| req.uri().path().to_owned());
     let method = req.method().clone();
 
+    gauge!("chronotheus_inflight_requests", "path" => path.clone()).increment(1.0);
+    counter!(
+        "chronotheus_requests_total",
+        "path" => path.clone(),
+        "method" => method.to_string(),
+    )
+    .increment(1);
+
     let response = next.run(req).await;
 
+    gauge!("chronotheus_inflight_requests", "path" => path.clone()).decrement(1.0);
+
     let duration = start.elapsed();
+    histogram!("chronotheus_request_duration_seconds", "path" => path.clone())
+        .record(duration.as_secs_f64());
+    counter!(
+        "chronotheus_responses_total",
+        "path" => path.clone(),
+        "status" => response.status().as_u16().to_string(),
+    )
+    .increment(1);
+
     info!("{} {} completed in {:?}", method, path, duration);
 
     response
@@ -1,11 +1,128 @@
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, Deserializer, Serializer};
+use serde::de::Error as DeError;
+use serde_json::Value;
 use std::collections::HashMap;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub timestamp: f64,
+    pub value: f64,
+}
+
+impl Serialize for Sample {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.timestamp, format_sample_value(self.value)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Sample {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (raw_timestamp, raw_value): (Value, Value) = Deserialize::deserialize(deserializer)?;
+
+        let timestamp = match raw_timestamp {
+            Value::Number(n) => n
+                .as_f64()
+                .ok_or_else(|| DeError::custom("sample timestamp is not a valid number"))?,
+            Value::String(s) => f64::from_str(&s).map_err(DeError::custom)?,
+            other => {
+                return Err(DeError::custom(format!(
+                    "unsupported sample timestamp: {:?}",
+                    other
+                )))
+            }
+        };
+
+        let value = match raw_value {
+            Value::String(s) => parse_sample_value(&s).map_err(DeError::custom)?,
+            Value::Number(n) => n
+                .as_f64()
+                .ok_or_else(|| DeError::custom("sample value is not a valid number"))?,
+            other => {
+                return Err(DeError::custom(format!(
+                    "unsupported sample value: {:?}",
+                    other
+                )))
+            }
+        };
+
+        Ok(Sample { timestamp, value })
+    }
+}
+
+/// Parses a Prometheus sample value string. `f64::from_str` already handles
+/// `"NaN"` and signed or unsigned infinities case-insensitively, including
+/// Prometheus's `"+Inf"`/`"-Inf"` wire format; the explicit `"+Inf"`/`"-Inf"`
+/// arms are only a fallback.
+fn parse_sample_value(s: &str) -> Result<f64, String> {
+    f64::from_str(s).or_else(|_| match s {
+        "+Inf" => Ok(f64::INFINITY),
+        "-Inf" => Ok(f64::NEG_INFINITY),
+        other => Err(format!("invalid sample value: {}", other)),
+    })
+}
+
+/// Re-serializes a sample value into Prometheus's canonical string form so
+/// downstream tools see `"NaN"`/`"+Inf"`/`"-Inf"` instead of Rust's spellings.
+pub fn format_sample_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Series {
     pub metric: HashMap<String, String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub value: Option<(i64, String)>,
+    pub value: Option<Sample>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub values: Option<Vec<(i64, String)>>,
-}
\ No newline at end of file
+    pub values: Option<Vec<Sample>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(json: &str) -> Sample {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn deserializes_fractional_timestamp_and_numeric_value() {
+        let sample = round_trip(r#"[1700000000.123, "1.5"]"#);
+        assert_eq!(sample.timestamp, 1700000000.123);
+        assert_eq!(sample.value, 1.5);
+    }
+
+    #[test]
+    fn deserializes_nan_value() {
+        let sample = round_trip(r#"[1700000000, "NaN"]"#);
+        assert!(sample.value.is_nan());
+    }
+
+    #[test]
+    fn deserializes_prometheus_style_infinities() {
+        assert_eq!(round_trip(r#"[1700000000, "+Inf"]"#).value, f64::INFINITY);
+        assert_eq!(round_trip(r#"[1700000000, "-Inf"]"#).value, f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn rejects_unparseable_value() {
+        let result: Result<Sample, _> = serde_json::from_str(r#"[1700000000, "not-a-number"]"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn format_sample_value_uses_prometheus_spellings() {
+        assert_eq!(format_sample_value(f64::NAN), "NaN");
+        assert_eq!(format_sample_value(f64::INFINITY), "+Inf");
+        assert_eq!(format_sample_value(f64::NEG_INFINITY), "-Inf");
+        assert_eq!(format_sample_value(1.5), "1.5");
+    }
+}
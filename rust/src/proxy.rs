@@ -1,12 +1,35 @@
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use reqwest::Client;
+use std::sync::Arc;
 use std::time::Duration;
+use metrics_exporter_prometheus::PrometheusHandle;
+use crate::cache::WindowCache;
+use crate::config::Config;
+use crate::upstream::{PrometheusHttpUpstream, Upstream};
 
 #[derive(Clone)]
 pub struct ChronoProxy {
     pub offsets: Vec<i64>,
     pub timeframes: Vec<String>,
+    pub metrics_handle: Option<PrometheusHandle>,
+    pub cache: WindowCache,
+    pub cache_ttl_current: Duration,
+    pub cache_ttl_historical: Duration,
+    pub upstream: Arc<dyn Upstream>,
+    /// How many times a transient fetch failure is retried, not counting
+    /// the initial attempt. See `crate::resilience::retry_with_backoff`.
+    pub max_retries: u32,
+    /// Base delay for the retry backoff, in milliseconds.
+    pub backoff_base_ms: u64,
+    /// Largest point count `fetch_windows_range` will request per
+    /// `query_range` call before chunking the interval. See
+    /// `Config::max_points`.
+    pub max_points: u64,
+    /// How many window fetches run concurrently. See
+    /// `Config::max_concurrent_fetches`.
+    pub max_concurrent_fetches: usize,
+    /// The `k` multiplier for the `zScoreAgainstLast28` command's
+    /// `mean ± k * stddev` anomaly bands. See `Config::zscore_band_multiplier`.
+    pub zscore_band_multiplier: f64,
 }
 
 impl ChronoProxy {
@@ -26,35 +49,66 @@ impl ChronoProxy {
                 "21days".into(),
                 "28days".into(),
             ],
+            metrics_handle: None,
+            cache: WindowCache::new(),
+            cache_ttl_current: Duration::from_secs(30),
+            cache_ttl_historical: Duration::from_secs(3600),
+            upstream: Arc::new(PrometheusHttpUpstream::new(
+                Client::new(),
+                "http://localhost:9090".to_string(),
+            )),
+            max_retries: 2,
+            backoff_base_ms: 200,
+            max_points: 11_000,
+            max_concurrent_fetches: 8,
+            zscore_band_multiplier: 2.0,
         }
     }
 
-    pub fn timeframes(&self) -> Vec<String> {
-        self.timeframes.clone()
+    /// Builds a `ChronoProxy` from a loaded `Config`, carrying over the
+    /// offsets, timeframes, cache TTLs, and upstream URL it specifies.
+    pub fn from_config(config: &Config) -> Self {
+        ChronoProxy {
+            offsets: config.offsets.clone(),
+            timeframes: config.timeframes.clone(),
+            metrics_handle: None,
+            cache: WindowCache::new(),
+            cache_ttl_current: Duration::from_secs(config.cache_ttl_current_secs),
+            cache_ttl_historical: Duration::from_secs(config.cache_ttl_historical_secs),
+            upstream: Arc::new(PrometheusHttpUpstream::new(
+                Client::new(),
+                config.upstream_url.clone(),
+            )),
+            max_retries: config.max_retries,
+            backoff_base_ms: config.backoff_base_ms,
+            max_points: config.max_points,
+            max_concurrent_fetches: config.max_concurrent_fetches,
+            zscore_band_multiplier: config.zscore_band_multiplier,
+        }
     }
-}
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PrometheusResponse<T> {
-    pub status: String,
-    pub data: PrometheusData<T>,
-}
+    pub fn with_metrics_handle(mut self, handle: PrometheusHandle) -> Self {
+        self.metrics_handle = Some(handle);
+        self
+    }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PrometheusData<T> {
-    #[serde(rename = "resultType")]
-    pub result_type: String,
-    pub result: Vec<T>,
-}
+    pub fn with_upstream(mut self, upstream: Arc<dyn Upstream>) -> Self {
+        self.upstream = upstream;
+        self
+    }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct InstantSeries {
-    pub metric: HashMap<String, String>,
-    pub value: (i64, String),
-}
+    pub fn timeframes(&self) -> Vec<String> {
+        self.timeframes.clone()
+    }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RangeSeries {
-    pub metric: HashMap<String, String>,
-    pub values: Vec<(i64, String)>,
+    /// The TTL to use for a window fetched at the given offset: the
+    /// `current` window (offset 0) is cached briefly, historical windows are
+    /// effectively immutable and cached much longer.
+    pub fn ttl_for_offset(&self, offset: i64) -> Duration {
+        if offset == 0 {
+            self.cache_ttl_current
+        } else {
+            self.cache_ttl_historical
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,126 @@
+use std::future::Future;
+use std::time::Duration;
+use log::warn;
+
+use crate::error::AppError;
+
+/// Retries `f` on transient upstream failures, waiting `backoff_base_ms *
+/// 2^attempt` between attempts, up to `max_retries` additional attempts
+/// past the first. Non-transient errors (see `AppError::is_transient`)
+/// are returned immediately without retrying. If every attempt fails, the
+/// last error is wrapped in `AppError::RetriesExhausted`.
+pub async fn retry_with_backoff<T, F, Fut>(
+    max_retries: u32,
+    backoff_base_ms: u64,
+    mut f: F,
+) -> Result<T, AppError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, AppError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(e) if !e.is_transient() => return Err(e),
+            Err(e) if attempt >= max_retries => {
+                return Err(AppError::RetriesExhausted(format!(
+                    "giving up after {} attempt(s): {}",
+                    attempt + 1,
+                    e
+                )));
+            }
+            Err(e) => {
+                let backoff = Duration::from_millis(
+                    backoff_base_ms.saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX)),
+                );
+                warn!(
+                    "transient upstream error on attempt {}/{}: {} (retrying in {:?})",
+                    attempt + 1,
+                    max_retries + 1,
+                    e,
+                    backoff,
+                );
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// A real `reqwest::Error` with `is_connect() == true`, so it round-trips
+    /// through `AppError::is_transient()` the same way a dropped upstream
+    /// connection would.
+    async fn transient_error() -> AppError {
+        let err = reqwest::get("http://127.0.0.1:1/")
+            .await
+            .expect_err("nothing should be listening on port 1");
+        AppError::Upstream(err)
+    }
+
+    #[tokio::test]
+    async fn succeeds_without_retrying_on_first_success() {
+        let attempts = AtomicU32::new(0);
+
+        let result = retry_with_backoff(2, 1, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async { Ok::<_, AppError>(42) }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors_until_success() {
+        let attempts = AtomicU32::new(0);
+
+        let result = retry_with_backoff(2, 1, || async {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            if attempt < 2 {
+                Err(transient_error().await)
+            } else {
+                Ok(42)
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn non_transient_errors_are_not_retried() {
+        let attempts = AtomicU32::new(0);
+
+        let result = retry_with_backoff(2, 1, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async { Err::<u32, _>(AppError::Backend("bad payload".to_string())) }
+        })
+        .await;
+
+        assert!(matches!(result, Err(AppError::Backend(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_are_wrapped_and_bounded() {
+        let attempts = AtomicU32::new(0);
+
+        let result = retry_with_backoff(2, 1, || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err::<u32, _>(transient_error().await)
+        })
+        .await;
+
+        assert!(matches!(result, Err(AppError::RetriesExhausted(_))));
+        // The initial attempt plus `max_retries` retries, never more.
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,42 @@
+use reqwest::Client;
+use rustls::{ClientConfig, RootCertStore};
+use std::time::Duration;
+
+/// Builds the shared HTTP client used for all upstream requests. When
+/// `upstream_tls` is set, the client trusts the OS's native root store
+/// (via `rustls-native-certs`) instead of reqwest's bundled roots, so
+/// Chronotheus can sit in front of a TLS-secured Prometheus without extra
+/// certificate configuration. `request_timeout_ms` bounds every request
+/// made with the client, so a single wedged upstream can't hang a fanned-
+/// out query indefinitely.
+pub fn build_http_client(upstream_tls: bool, request_timeout_ms: u64) -> Client {
+    let timeout = Duration::from_millis(request_timeout_ms);
+
+    if !upstream_tls {
+        return Client::builder()
+            .timeout(timeout)
+            .connect_timeout(timeout)
+            .build()
+            .expect("failed to build HTTP client");
+    }
+
+    let mut roots = RootCertStore::empty();
+    for cert in rustls_native_certs::load_native_certs()
+        .expect("failed to load native root certificates")
+    {
+        roots
+            .add(cert)
+            .expect("failed to add native root certificate");
+    }
+
+    let tls_config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    Client::builder()
+        .use_preconfigured_tls(tls_config)
+        .timeout(timeout)
+        .connect_timeout(timeout)
+        .build()
+        .expect("failed to build TLS-enabled HTTP client")
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use crate::error::AppError;
+use crate::models::{Sample, Series};
+use crate::utils::build_query_string;
+
+/// A historical-data backend Chronotheus can fan out queries to. The
+/// default implementation talks to a Prometheus-compatible HTTP API;
+/// alternate backends (Thanos/Cortex, a local TSDB, or a mock for tests)
+/// implement the same trait.
+#[async_trait]
+pub trait Upstream: Send + Sync {
+    async fn instant_query(&self, params: &HashMap<String, Vec<String>>) -> Result<Vec<Series>, AppError>;
+    async fn range_query(&self, params: &HashMap<String, Vec<String>>) -> Result<Vec<Series>, AppError>;
+    async fn labels(&self) -> Result<Vec<String>, AppError>;
+    async fn label_values(&self, label: &str) -> Result<Vec<String>, AppError>;
+}
+
+/// Talks to a Prometheus (or Prometheus-API-compatible) server over HTTP.
+pub struct PrometheusHttpUpstream {
+    client: Client,
+    base_url: String,
+}
+
+impl PrometheusHttpUpstream {
+    pub fn new(client: Client, base_url: String) -> Self {
+        Self { client, base_url }
+    }
+}
+
+/// Typed envelope for a Prometheus `/api/v1/query{,_range}` response. The
+/// `resultType` tag is matched up front instead of the result shape being
+/// guessed from which endpoint was called, and `status: "error"` carries
+/// the upstream's own error message instead of failing to deserialize
+/// `data` with no explanation.
+#[derive(Debug, Deserialize)]
+struct PromResponse {
+    status: String,
+    #[serde(default)]
+    data: Option<PromData>,
+    #[serde(rename = "errorType", default)]
+    error_type: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "resultType", content = "result", rename_all = "lowercase")]
+enum PromData {
+    Vector(Vec<Series>),
+    Matrix(Vec<Series>),
+    Scalar(Sample),
+    String((f64, String)),
+}
+
+impl PromResponse {
+    fn into_series(self) -> Result<Vec<Series>, AppError> {
+        if self.status == "error" {
+            return Err(AppError::Backend(format_upstream_error(self.error_type, self.error)));
+        }
+        match self.data {
+            Some(PromData::Vector(series)) | Some(PromData::Matrix(series)) => Ok(series),
+            Some(PromData::Scalar(_)) | Some(PromData::String(_)) => Err(AppError::Backend(
+                "expected a vector or matrix result, got a scalar/string".to_string(),
+            )),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Envelope for `/api/v1/labels` and `/api/v1/label/{name}/values`, whose
+/// `data` is a flat list of strings rather than a `resultType`-tagged
+/// result.
+#[derive(Debug, Deserialize)]
+struct PromLabelsResponse {
+    status: String,
+    #[serde(default)]
+    data: Vec<String>,
+    #[serde(rename = "errorType", default)]
+    error_type: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+impl PromLabelsResponse {
+    fn into_values(self) -> Result<Vec<String>, AppError> {
+        if self.status == "error" {
+            return Err(AppError::Backend(format_upstream_error(self.error_type, self.error)));
+        }
+        Ok(self.data)
+    }
+}
+
+fn format_upstream_error(error_type: Option<String>, error: Option<String>) -> String {
+    match (error_type, error) {
+        (Some(t), Some(e)) => format!("{}: {}", t, e),
+        (None, Some(e)) => e,
+        (Some(t), None) => t,
+        (None, None) => "unknown upstream error".to_string(),
+    }
+}
+
+#[async_trait]
+impl Upstream for PrometheusHttpUpstream {
+    async fn instant_query(&self, params: &HashMap<String, Vec<String>>) -> Result<Vec<Series>, AppError> {
+        let query_string = build_query_string(params);
+        let url = format!("{}/api/v1/query?{}", self.base_url, query_string);
+        let response = self.client.get(&url).send().await?.json::<PromResponse>().await?;
+        response.into_series()
+    }
+
+    async fn range_query(&self, params: &HashMap<String, Vec<String>>) -> Result<Vec<Series>, AppError> {
+        let query_string = build_query_string(params);
+        let url = format!("{}/api/v1/query_range?{}", self.base_url, query_string);
+        let response = self.client.get(&url).send().await?.json::<PromResponse>().await?;
+        response.into_series()
+    }
+
+    async fn labels(&self) -> Result<Vec<String>, AppError> {
+        let url = format!("{}/api/v1/labels", self.base_url);
+        let response = self.client.get(&url).send().await?.json::<PromLabelsResponse>().await?;
+        response.into_values()
+    }
+
+    async fn label_values(&self, label: &str) -> Result<Vec<String>, AppError> {
+        let url = format!("{}/api/v1/label/{}/values", self.base_url, label);
+        let response = self.client.get(&url).send().await?.json::<PromLabelsResponse>().await?;
+        response.into_values()
+    }
+}
+
+/// Talks to a Prometheus datasource through Grafana's datasource-proxy
+/// route (`/api/datasources/proxy/{id}/...`) instead of dialing Prometheus
+/// directly, authenticating with a Grafana service-account bearer token.
+/// Useful when Chronotheus only has network access to Grafana.
+pub struct GrafanaProxyUpstream {
+    client: Client,
+    grafana_base_url: String,
+    datasource_id: u64,
+    api_token: String,
+}
+
+impl GrafanaProxyUpstream {
+    pub fn new(client: Client, grafana_base_url: String, datasource_id: u64, api_token: String) -> Self {
+        Self { client, grafana_base_url, datasource_id, api_token }
+    }
+
+    fn proxy_url(&self, path_and_query: &str) -> String {
+        format!(
+            "{}/api/datasources/proxy/{}{}",
+            self.grafana_base_url, self.datasource_id, path_and_query
+        )
+    }
+}
+
+#[async_trait]
+impl Upstream for GrafanaProxyUpstream {
+    async fn instant_query(&self, params: &HashMap<String, Vec<String>>) -> Result<Vec<Series>, AppError> {
+        let query_string = build_query_string(params);
+        let url = self.proxy_url(&format!("/api/v1/query?{}", query_string));
+        let response = self.client.get(&url).bearer_auth(&self.api_token).send().await?.json::<PromResponse>().await?;
+        response.into_series()
+    }
+
+    async fn range_query(&self, params: &HashMap<String, Vec<String>>) -> Result<Vec<Series>, AppError> {
+        let query_string = build_query_string(params);
+        let url = self.proxy_url(&format!("/api/v1/query_range?{}", query_string));
+        let response = self.client.get(&url).bearer_auth(&self.api_token).send().await?.json::<PromResponse>().await?;
+        response.into_series()
+    }
+
+    async fn labels(&self) -> Result<Vec<String>, AppError> {
+        let url = self.proxy_url("/api/v1/labels");
+        let response = self.client.get(&url).bearer_auth(&self.api_token).send().await?.json::<PromLabelsResponse>().await?;
+        response.into_values()
+    }
+
+    async fn label_values(&self, label: &str) -> Result<Vec<String>, AppError> {
+        let url = self.proxy_url(&format!("/api/v1/label/{}/values", label));
+        let response = self.client.get(&url).bearer_auth(&self.api_token).send().await?.json::<PromLabelsResponse>().await?;
+        response.into_values()
+    }
+}
@@ -1,11 +1,14 @@
 use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use futures::stream::{self, StreamExt};
 use regex::Regex;
-use reqwest::Client;
-use serde_json::Value;
-use crate::models::Series;
+use metrics::{counter, gauge, histogram};
+use crate::cache::CacheKey;
+use crate::models::{Sample, Series};
 use crate::proxy::ChronoProxy;
 
 pub fn dedupe_series(series: Vec<Series>) -> Vec<Series> {
+    let before = series.len();
     let mut seen = HashSet::new();
     let mut result = Vec::new();
 
@@ -16,6 +19,10 @@ pub fn dedupe_series(series: Vec<Series>) -> Vec<Series> {
             result.push(s);
         }
     }
+
+    gauge!("chronotheus_series_before_dedupe").set(before as f64);
+    gauge!("chronotheus_series_after_dedupe").set(result.len() as f64);
+
     result
 }
 
@@ -51,13 +58,8 @@ pub fn build_last_month_average(series: &[Series], is_range: bool) -> Vec<Series
         
         let avg_series = if is_range {
             let values = s.values.as_ref().unwrap();
-            let avg_values: Vec<(i64, String)> = values.iter()
-                .map(|(ts, val)| {
-                    let num_val = val.parse::<f64>().unwrap_or(0.0);
-                    (*ts, format!("{:.3}", num_val))
-                })
-                .collect();
-            
+            let avg_values: Vec<Sample> = values.iter().copied().collect();
+
             Series {
                 metric: avg_metric,
                 value: None,
@@ -67,7 +69,7 @@ pub fn build_last_month_average(series: &[Series], is_range: bool) -> Vec<Series
             let val = s.value.as_ref().unwrap();
             Series {
                 metric: avg_metric,
-                value: Some((val.0, val.1.clone())),
+                value: Some(*val),
                 values: None,
             }
         };
@@ -131,14 +133,13 @@ pub fn append_compare(
             let compare_series = if is_range {
                 let cur_values = cur.values.as_ref().unwrap();
                 let avg_values = avg.values.as_ref().unwrap();
-                
-                let compare_values: Vec<(i64, String)> = cur_values
+
+                let compare_values: Vec<Sample> = cur_values
                     .iter()
                     .zip(avg_values.iter())
-                    .map(|((ts, cur_val), (_, avg_val))| {
-                        let cur_num = cur_val.parse::<f64>().unwrap_or(0.0);
-                        let avg_num = avg_val.parse::<f64>().unwrap_or(0.0);
-                        (*ts, format!("{:.3}", cur_num - avg_num))
+                    .map(|(cur_sample, avg_sample)| Sample {
+                        timestamp: cur_sample.timestamp,
+                        value: cur_sample.value - avg_sample.value,
                     })
                     .collect();
 
@@ -148,15 +149,15 @@ pub fn append_compare(
                     values: Some(compare_values),
                 }
             } else {
-                let (cur_ts, cur_val) = cur.value.as_ref().unwrap();
-                let (_, avg_val) = avg.value.as_ref().unwrap();
-                
-                let cur_num = cur_val.parse::<f64>().unwrap_or(0.0);
-                let avg_num = avg_val.parse::<f64>().unwrap_or(0.0);
-                
+                let cur_sample = cur.value.as_ref().unwrap();
+                let avg_sample = avg.value.as_ref().unwrap();
+
                 Series {
                     metric: compare_metric,
-                    value: Some((*cur_ts, format!("{:.3}", cur_num - avg_num))),
+                    value: Some(Sample {
+                        timestamp: cur_sample.timestamp,
+                        value: cur_sample.value - avg_sample.value,
+                    }),
                     values: None,
                 }
             };
@@ -190,19 +191,20 @@ pub fn append_percent(
             let percent_series = if is_range {
                 let cur_values = cur.values.as_ref().unwrap();
                 let avg_values = avg.values.as_ref().unwrap();
-                
-                let percent_values: Vec<(i64, String)> = cur_values
+
+                let percent_values: Vec<Sample> = cur_values
                     .iter()
                     .zip(avg_values.iter())
-                    .map(|((ts, cur_val), (_, avg_val))| {
-                        let cur_num = cur_val.parse::<f64>().unwrap_or(0.0);
-                        let avg_num = avg_val.parse::<f64>().unwrap_or(0.0);
-                        let percent = if avg_num != 0.0 {
-                            ((cur_num - avg_num) / avg_num) * 100.0
+                    .map(|(cur_sample, avg_sample)| {
+                        let percent = if avg_sample.value != 0.0 {
+                            ((cur_sample.value - avg_sample.value) / avg_sample.value) * 100.0
                         } else {
                             0.0
                         };
-                        (*ts, format!("{:.3}", percent))
+                        Sample {
+                            timestamp: cur_sample.timestamp,
+                            value: percent,
+                        }
                     })
                     .collect();
 
@@ -212,20 +214,20 @@ pub fn append_percent(
                     values: Some(percent_values),
                 }
             } else {
-                let (cur_ts, cur_val) = cur.value.as_ref().unwrap();
-                let (_, avg_val) = avg.value.as_ref().unwrap();
-                
-                let cur_num = cur_val.parse::<f64>().unwrap_or(0.0);
-                let avg_num = avg_val.parse::<f64>().unwrap_or(0.0);
-                let percent = if avg_num != 0.0 {
-                    ((cur_num - avg_num) / avg_num) * 100.0
+                let cur_sample = cur.value.as_ref().unwrap();
+                let avg_sample = avg.value.as_ref().unwrap();
+                let percent = if avg_sample.value != 0.0 {
+                    ((cur_sample.value - avg_sample.value) / avg_sample.value) * 100.0
                 } else {
                     0.0
                 };
-                
+
                 Series {
                     metric: percent_metric,
-                    value: Some((*cur_ts, format!("{:.3}", percent))),
+                    value: Some(Sample {
+                        timestamp: cur_sample.timestamp,
+                        value: percent,
+                    }),
                     values: None,
                 }
             };
@@ -237,6 +239,164 @@ pub fn append_percent(
     series
 }
 
+/// Groups `series` (as returned by `fetch_windows_instant`/
+/// `fetch_windows_range`, i.e. one entry per offset/timeframe per metric)
+/// by signature — metric labels with `chrono_timeframe` stripped, exactly
+/// as `index_by_signature` keys its maps — splitting each group into its
+/// `current` window and the rest of the historical offset windows.
+fn group_by_signature<'a>(series: &'a [Series]) -> HashMap<String, (Option<&'a Series>, Vec<&'a Series>)> {
+    let mut grouped: HashMap<String, (Option<&'a Series>, Vec<&'a Series>)> = HashMap::new();
+
+    for s in series {
+        let mut sig = s.metric.clone();
+        sig.remove("chrono_timeframe");
+        let key = serde_json::to_string(&sig).unwrap();
+        let entry = grouped.entry(key).or_insert((None, Vec::new()));
+
+        if s.metric.get("chrono_timeframe").map_or(false, |tf| tf == "current") {
+            entry.0 = Some(s);
+        } else {
+            entry.1.push(s);
+        }
+    }
+
+    grouped
+}
+
+/// Sample mean and sample standard deviation (Bessel's correction, N-1)
+/// of `values`. Callers must ensure `values.len() >= 2`.
+fn mean_and_stddev(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance.sqrt())
+}
+
+fn zscore(value: f64, mean: f64, stddev: f64) -> f64 {
+    if stddev == 0.0 {
+        0.0
+    } else {
+        (value - mean) / stddev
+    }
+}
+
+/// Computes a per-signature z-score of the `current` window against the
+/// distribution of the historical offset windows ("7days", "14days", ...),
+/// plus `mean ± band_multiplier * stddev` upper/lower anomaly bands.
+/// Requires at least two historical windows per signature; series with
+/// fewer are left out rather than dividing by a degenerate sample. Only
+/// applies for the `zScoreAgainstLast28` command.
+pub fn append_zscore(
+    mut series: Vec<Series>,
+    all_series: &[Series],
+    command: &str,
+    is_range: bool,
+    band_multiplier: f64,
+) -> Vec<Series> {
+    if command != "zScoreAgainstLast28" {
+        return series;
+    }
+
+    for (current, historical) in group_by_signature(all_series).values() {
+        let current = match current {
+            Some(c) => c,
+            None => continue,
+        };
+        if historical.len() < 2 {
+            continue;
+        }
+
+        let mut zscore_metric = current.metric.clone();
+        zscore_metric.insert("chrono_timeframe".to_string(), "zScoreAgainstLast28".to_string());
+        let mut upper_metric = current.metric.clone();
+        upper_metric.insert("chrono_timeframe".to_string(), "zScoreAgainstLast28Upper".to_string());
+        let mut lower_metric = current.metric.clone();
+        lower_metric.insert("chrono_timeframe".to_string(), "zScoreAgainstLast28Lower".to_string());
+
+        if is_range {
+            let current_values = match current.values.as_ref() {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let mut zscore_values = Vec::with_capacity(current_values.len());
+            let mut upper_values = Vec::with_capacity(current_values.len());
+            let mut lower_values = Vec::with_capacity(current_values.len());
+
+            for (idx, cur_sample) in current_values.iter().enumerate() {
+                let historical_values: Vec<f64> = historical
+                    .iter()
+                    .filter_map(|h| h.values.as_ref().and_then(|v| v.get(idx)).map(|s| s.value))
+                    .collect();
+                if historical_values.len() < 2 {
+                    continue;
+                }
+
+                let (mean, stddev) = mean_and_stddev(&historical_values);
+
+                zscore_values.push(Sample {
+                    timestamp: cur_sample.timestamp,
+                    value: zscore(cur_sample.value, mean, stddev),
+                });
+                upper_values.push(Sample {
+                    timestamp: cur_sample.timestamp,
+                    value: mean + band_multiplier * stddev,
+                });
+                lower_values.push(Sample {
+                    timestamp: cur_sample.timestamp,
+                    value: mean - band_multiplier * stddev,
+                });
+            }
+
+            series.push(Series { metric: zscore_metric, value: None, values: Some(zscore_values) });
+            series.push(Series { metric: upper_metric, value: None, values: Some(upper_values) });
+            series.push(Series { metric: lower_metric, value: None, values: Some(lower_values) });
+        } else {
+            let cur_sample = match current.value.as_ref() {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let historical_values: Vec<f64> = historical
+                .iter()
+                .filter_map(|h| h.value.as_ref().map(|s| s.value))
+                .collect();
+            if historical_values.len() < 2 {
+                continue;
+            }
+
+            let (mean, stddev) = mean_and_stddev(&historical_values);
+
+            series.push(Series {
+                metric: zscore_metric,
+                value: Some(Sample {
+                    timestamp: cur_sample.timestamp,
+                    value: zscore(cur_sample.value, mean, stddev),
+                }),
+                values: None,
+            });
+            series.push(Series {
+                metric: upper_metric,
+                value: Some(Sample {
+                    timestamp: cur_sample.timestamp,
+                    value: mean + band_multiplier * stddev,
+                }),
+                values: None,
+            });
+            series.push(Series {
+                metric: lower_metric,
+                value: Some(Sample {
+                    timestamp: cur_sample.timestamp,
+                    value: mean - band_multiplier * stddev,
+                }),
+                values: None,
+            });
+        }
+    }
+
+    series
+}
+
 pub fn filter_by_timeframe(series: Vec<Series>, timeframe: &str) -> Vec<Series> {
     series
         .into_iter()
@@ -248,12 +408,15 @@ pub async fn fetch_windows_instant(
     proxy: &ChronoProxy,
     params: &HashMap<String, Vec<String>>,
 ) -> anyhow::Result<Vec<Series>> {
-    let client = Client::new();
-    let mut all_series = Vec::new();
+    let base_query = params.get("query").and_then(|v| v.first()).cloned().unwrap_or_default();
 
-    for (offset, timeframe) in proxy.offsets.iter().zip(proxy.timeframes.iter()) {
+    let fetches = proxy.offsets.iter().zip(proxy.timeframes.iter()).enumerate().map(|(index, (&offset, timeframe))| {
+        let upstream = proxy.upstream.clone();
+        let cache = proxy.cache.clone();
+        let ttl = proxy.ttl_for_offset(offset);
+        let timeframe = timeframe.clone();
         let mut window_params = params.clone();
-        
+
         // Adjust time parameter if present
         if let Some(time) = window_params.get_mut("time") {
             if let Some(t) = time.first_mut() {
@@ -270,15 +433,83 @@ pub async fn fetch_windows_instant(
             }
         }
 
-        let query_string = build_query_string(&window_params);
-        let url = format!("http://localhost:9090/api/v1/query?{}", query_string);
+        let cache_key = CacheKey {
+            query: base_query.clone(),
+            offset,
+            time: window_params.get("time").and_then(|v| v.first()).cloned(),
+            start: None,
+            end: None,
+            step: None,
+        };
+
+        let max_retries = proxy.max_retries;
+        let backoff_base_ms = proxy.backoff_base_ms;
 
-        let response = client.get(&url).send().await?.json::<Value>().await?;
-        
-        if let Some(result) = response.get("data").and_then(|d| d.get("result")) {
-            let series: Vec<Series> = serde_json::from_value(result.clone())?;
-            all_series.extend(series);
+        async move {
+            if let Some(cached) = cache.get(&cache_key) {
+                return Ok((index, cached));
+            }
+
+            let fetch_start = Instant::now();
+            let fetch_result = crate::resilience::retry_with_backoff(max_retries, backoff_base_ms, || {
+                upstream.instant_query(&window_params)
+            })
+            .await;
+            histogram!(
+                "chronotheus_upstream_fetch_duration_seconds",
+                "offset" => offset.to_string(),
+                "timeframe" => timeframe.clone(),
+            )
+            .record(fetch_start.elapsed().as_secs_f64());
+
+            match fetch_result {
+                Ok(series) => {
+                    cache.put(cache_key, series.clone(), ttl);
+                    counter!(
+                        "chronotheus_windows_fetched_total",
+                        "offset" => offset.to_string(),
+                        "timeframe" => timeframe.clone(),
+                    )
+                    .increment(1);
+                    Ok((index, series))
+                }
+                Err(e) => {
+                    counter!(
+                        "chronotheus_upstream_errors_total",
+                        "offset" => offset.to_string(),
+                        "timeframe" => timeframe.clone(),
+                    )
+                    .increment(1);
+
+                    // The live ("current") window is the user's actual
+                    // query; its failure must propagate, not be silently
+                    // traded for an empty series. Only the historical
+                    // comparison windows are allowed to degrade.
+                    if offset == 0 {
+                        return Err(anyhow::Error::new(e).context("fetching current window"));
+                    }
+
+                    log::warn!("dropping timeframe \"{}\" after fetch failure: {}", timeframe, e);
+                    Ok((index, Vec::new()))
+                }
+            }
         }
+    });
+
+    let results: Vec<anyhow::Result<(usize, Vec<Series>)>> = stream::iter(fetches)
+        .buffer_unordered(proxy.max_concurrent_fetches.max(1))
+        .collect()
+        .await;
+
+    let mut windows = Vec::with_capacity(results.len());
+    for result in results {
+        windows.push(result?);
+    }
+    windows.sort_by_key(|(index, _)| *index);
+
+    let mut all_series = Vec::new();
+    for (_, series) in windows {
+        all_series.extend(series);
     }
 
     Ok(all_series)
@@ -288,12 +519,15 @@ pub async fn fetch_windows_range(
     proxy: &ChronoProxy,
     params: &HashMap<String, Vec<String>>,
 ) -> anyhow::Result<Vec<Series>> {
-    let client = Client::new();
-    let mut all_series = Vec::new();
+    let base_query = params.get("query").and_then(|v| v.first()).cloned().unwrap_or_default();
 
-    for (offset, timeframe) in proxy.offsets.iter().zip(proxy.timeframes.iter()) {
+    let fetches = proxy.offsets.iter().zip(proxy.timeframes.iter()).enumerate().map(|(index, (&offset, timeframe))| {
+        let upstream = proxy.upstream.clone();
+        let cache = proxy.cache.clone();
+        let ttl = proxy.ttl_for_offset(offset);
+        let timeframe = timeframe.clone();
         let mut window_params = params.clone();
-        
+
         // Adjust start/end parameters
         for param in ["start", "end"].iter() {
             if let Some(time) = window_params.get_mut(*param) {
@@ -312,20 +546,198 @@ pub async fn fetch_windows_range(
             }
         }
 
-        let query_string = build_query_string(&window_params);
-        let url = format!("http://localhost:9090/api/v1/query_range?{}", query_string);
+        let cache_key = CacheKey {
+            query: base_query.clone(),
+            offset,
+            time: None,
+            start: window_params.get("start").and_then(|v| v.first()).cloned(),
+            end: window_params.get("end").and_then(|v| v.first()).cloned(),
+            step: window_params.get("step").and_then(|v| v.first()).cloned(),
+        };
+
+        let max_retries = proxy.max_retries;
+        let backoff_base_ms = proxy.backoff_base_ms;
+        let max_points = proxy.max_points;
 
-        let response = client.get(&url).send().await?.json::<Value>().await?;
-        
-        if let Some(result) = response.get("data").and_then(|d| d.get("result")) {
-            let series: Vec<Series> = serde_json::from_value(result.clone())?;
-            all_series.extend(series);
+        async move {
+            if let Some(cached) = cache.get(&cache_key) {
+                return Ok((index, cached));
+            }
+
+            let sub_windows = chunk_range_params(&window_params, max_points);
+
+            let fetch_start = Instant::now();
+            let mut chunks = Vec::with_capacity(sub_windows.len());
+            for sub_params in &sub_windows {
+                let fetch_result = crate::resilience::retry_with_backoff(max_retries, backoff_base_ms, || {
+                    upstream.range_query(sub_params)
+                })
+                .await;
+
+                match fetch_result {
+                    Ok(series) => chunks.push(series),
+                    Err(e) => {
+                        counter!(
+                            "chronotheus_upstream_errors_total",
+                            "offset" => offset.to_string(),
+                            "timeframe" => timeframe.clone(),
+                        )
+                        .increment(1);
+
+                        // The live ("current") window is the user's
+                        // actual query; a sub-window failure there must
+                        // propagate rather than silently shrink the
+                        // result. Only historical comparison windows are
+                        // allowed to degrade.
+                        if offset == 0 {
+                            return Err(anyhow::Error::new(e).context("fetching current window"));
+                        }
+
+                        log::warn!(
+                            "dropping a sub-window of timeframe \"{}\" after fetch failure: {}",
+                            timeframe,
+                            e
+                        );
+                    }
+                }
+            }
+            histogram!(
+                "chronotheus_upstream_fetch_duration_seconds",
+                "offset" => offset.to_string(),
+                "timeframe" => timeframe.clone(),
+            )
+            .record(fetch_start.elapsed().as_secs_f64());
+
+            let series = merge_chunked_series(chunks);
+            cache.put(cache_key, series.clone(), ttl);
+            counter!(
+                "chronotheus_windows_fetched_total",
+                "offset" => offset.to_string(),
+                "timeframe" => timeframe.clone(),
+            )
+            .increment(1);
+
+            Ok((index, series))
         }
+    });
+
+    let results: Vec<anyhow::Result<(usize, Vec<Series>)>> = stream::iter(fetches)
+        .buffer_unordered(proxy.max_concurrent_fetches.max(1))
+        .collect()
+        .await;
+
+    let mut windows = Vec::with_capacity(results.len());
+    for result in results {
+        windows.push(result?);
+    }
+    windows.sort_by_key(|(index, _)| *index);
+
+    let mut all_series = Vec::new();
+    for (_, series) in windows {
+        all_series.extend(series);
     }
 
     Ok(all_series)
 }
 
+/// Splits `start..end` into consecutive sub-windows of at most
+/// `(max_points - 1) * step` seconds each, so no sub-window asks
+/// Prometheus for more than `max_points` samples per series.
+/// The `-1` accounts for Prometheus counting `(end-start)/step + 1`
+/// points for an inclusive range — without it, a chunk spanning exactly
+/// `max_points * step` seconds would itself request `max_points + 1`
+/// points, reproducing the resolution error this function exists to
+/// avoid. Returns a single-element vec of the original params unchanged
+/// if `start`, `end`, or `step` can't be parsed, or if `(end - start) /
+/// step` does not exceed `max_points`; such an unsplit range can still
+/// cover `max_points + 1` inclusive points.
+fn chunk_range_params(
+    params: &HashMap<String, Vec<String>>,
+    max_points: u64,
+) -> Vec<HashMap<String, Vec<String>>> {
+    let parsed = params
+        .get("start")
+        .and_then(|v| v.first())
+        .and_then(|s| s.parse::<i64>().ok())
+        .zip(params.get("end").and_then(|v| v.first()).and_then(|s| s.parse::<i64>().ok()))
+        .zip(params.get("step").and_then(|v| v.first()).and_then(|s| s.parse::<i64>().ok()))
+        .map(|((start, end), step)| (start, end, step));
+
+    let (start, end, step) = match parsed {
+        Some(t) => t,
+        None => return vec![params.clone()],
+    };
+
+    if step <= 0 || max_points == 0 || end <= start {
+        return vec![params.clone()];
+    }
+
+    let expected_points = (end - start) as u64 / step as u64;
+    if expected_points <= max_points {
+        return vec![params.clone()];
+    }
+
+    // Saturate at one step per chunk so a pathological `max_points <= 1`
+    // can't produce a zero-length span and loop forever.
+    let chunk_span = max_points.saturating_sub(1).max(1) as i64 * step;
+    let mut windows = Vec::new();
+    let mut chunk_start = start;
+    while chunk_start < end {
+        let chunk_end = (chunk_start + chunk_span).min(end);
+        let mut chunk_params = params.clone();
+        chunk_params.insert("start".to_string(), vec![chunk_start.to_string()]);
+        chunk_params.insert("end".to_string(), vec![chunk_end.to_string()]);
+        windows.push(chunk_params);
+        if chunk_end >= end {
+            break;
+        }
+        chunk_start = chunk_end;
+    }
+    windows
+}
+
+/// Stitches the per-sub-window results of a chunked `query_range` back
+/// into one series list, keyed by each series' metric labels (which
+/// already carry the injected `chrono_timeframe` label unchanged). A
+/// series missing from some chunks is still emitted (left/right-join),
+/// and overlapping boundary timestamps between adjacent chunks are
+/// deduped.
+fn merge_chunked_series(chunks: Vec<Vec<Series>>) -> Vec<Series> {
+    let mut order = Vec::new();
+    let mut merged: HashMap<String, Series> = HashMap::new();
+
+    for chunk in chunks {
+        for series in chunk {
+            let key = serde_json::to_string(&series.metric).unwrap_or_default();
+            match merged.get_mut(&key) {
+                Some(existing) => {
+                    let mut values = existing.values.take().unwrap_or_default();
+                    values.extend(series.values.unwrap_or_default());
+                    existing.values = Some(values);
+                }
+                None => {
+                    order.push(key.clone());
+                    merged.insert(key, series);
+                }
+            }
+        }
+    }
+
+    order
+        .into_iter()
+        .filter_map(|key| merged.remove(&key))
+        .map(|mut series| {
+            if let Some(values) = series.values.as_mut() {
+                values.sort_by(|a, b| {
+                    a.timestamp.partial_cmp(&b.timestamp).unwrap_or(std::cmp::Ordering::Equal)
+                });
+                values.dedup_by(|a, b| a.timestamp == b.timestamp);
+            }
+            series
+        })
+        .collect()
+}
+
 pub fn parse_params(query_string: &str) -> HashMap<String, Vec<String>> {
     let mut params = HashMap::new();
     
@@ -363,11 +775,192 @@ pub fn build_query_string(params: &HashMap<String, Vec<String>>) -> String {
         .iter()
         .flat_map(|(key, values)| {
             values.iter().map(move |value| {
-                format!("{}={}", 
+                format!("{}={}",
                     urlencoding::encode(key),
                     urlencoding::encode(value))
             })
         })
         .collect::<Vec<_>>()
         .join("&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(start: i64, end: i64, step: i64) -> HashMap<String, Vec<String>> {
+        let mut params = HashMap::new();
+        params.insert("start".to_string(), vec![start.to_string()]);
+        params.insert("end".to_string(), vec![end.to_string()]);
+        params.insert("step".to_string(), vec![step.to_string()]);
+        params
+    }
+
+    #[test]
+    fn chunk_range_params_leaves_small_ranges_untouched() {
+        let p = params(0, 1000, 10);
+        let windows = chunk_range_params(&p, 1000);
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0], p);
+    }
+
+    #[test]
+    fn chunk_range_params_splits_ranges_over_the_point_limit() {
+        // 1001 points at step 10 => needs chunks of at most 200 points
+        // each, i.e. a span of (200 - 1) * 10 = 1990 seconds.
+        let p = params(0, 10_000, 10);
+        let windows = chunk_range_params(&p, 200);
+
+        assert_eq!(windows.len(), 6);
+        assert_eq!(windows[0]["start"], vec!["0".to_string()]);
+        assert_eq!(windows[0]["end"], vec!["1990".to_string()]);
+        // Consecutive chunks abut (sharing their boundary timestamp, which
+        // `merge_chunked_series` dedupes) so no sample is skipped.
+        assert_eq!(windows[1]["start"], windows[0]["end"]);
+        assert_eq!(windows[5]["end"], vec!["10000".to_string()]);
+
+        // Every chunk must request at most `max_points` points: Prometheus
+        // counts (end-start)/step + 1 inclusive points per sub-window.
+        for w in &windows {
+            let start: i64 = w["start"][0].parse().unwrap();
+            let end: i64 = w["end"][0].parse().unwrap();
+            let points = (end - start) / 10 + 1;
+            assert!(points <= 200, "chunk [{}, {}] requests {} points", start, end, points);
+        }
+    }
+
+    #[test]
+    fn chunk_range_params_falls_back_when_unparseable() {
+        let mut p = HashMap::new();
+        p.insert("start".to_string(), vec!["not-a-number".to_string()]);
+        p.insert("end".to_string(), vec!["1000".to_string()]);
+        p.insert("step".to_string(), vec!["10".to_string()]);
+
+        let windows = chunk_range_params(&p, 1);
+        assert_eq!(windows, vec![p]);
+    }
+
+    fn series_with(metric_name: &str, values: &[(f64, f64)]) -> Series {
+        let mut metric = HashMap::new();
+        metric.insert("__name__".to_string(), metric_name.to_string());
+        Series {
+            metric,
+            value: None,
+            values: Some(
+                values
+                    .iter()
+                    .map(|&(timestamp, value)| Sample { timestamp, value })
+                    .collect(),
+            ),
+        }
+    }
+
+    #[test]
+    fn merge_chunked_series_dedupes_overlapping_boundary_timestamps() {
+        let chunk_a = vec![series_with("up", &[(0.0, 1.0), (10.0, 2.0)])];
+        // Chunk boundaries are inclusive on both ends, so timestamp 10.0
+        // is fetched twice.
+        let chunk_b = vec![series_with("up", &[(10.0, 2.0), (20.0, 3.0)])];
+
+        let merged = merge_chunked_series(vec![chunk_a, chunk_b]);
+
+        assert_eq!(merged.len(), 1);
+        let values = merged[0].values.as_ref().unwrap();
+        assert_eq!(
+            values.iter().map(|s| s.timestamp).collect::<Vec<_>>(),
+            vec![0.0, 10.0, 20.0]
+        );
+    }
+
+    #[test]
+    fn merge_chunked_series_keeps_series_missing_from_some_chunks() {
+        let chunk_a = vec![
+            series_with("up", &[(0.0, 1.0)]),
+            series_with("down", &[(0.0, 0.0)]),
+        ];
+        let chunk_b = vec![series_with("up", &[(10.0, 1.0)])];
+
+        let merged = merge_chunked_series(vec![chunk_a, chunk_b]);
+
+        let names: Vec<&str> = merged
+            .iter()
+            .map(|s| s.metric["__name__"].as_str())
+            .collect();
+        assert_eq!(names, vec!["up", "down"]);
+    }
+
+    #[test]
+    fn mean_and_stddev_computes_sample_statistics() {
+        let (mean, stddev) = mean_and_stddev(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert!((mean - 5.0).abs() < 1e-9);
+        assert!((stddev - 2.138089935).abs() < 1e-6);
+    }
+
+    #[test]
+    fn zscore_returns_zero_on_degenerate_stddev() {
+        assert_eq!(zscore(10.0, 5.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn zscore_computes_standard_score() {
+        assert_eq!(zscore(9.0, 5.0, 2.0), 2.0);
+    }
+
+    fn instant_series(name: &str, timeframe: &str, value: f64) -> Series {
+        let mut metric = HashMap::new();
+        metric.insert("__name__".to_string(), name.to_string());
+        metric.insert("chrono_timeframe".to_string(), timeframe.to_string());
+        Series {
+            metric,
+            value: Some(Sample { timestamp: 1700000000.0, value }),
+            values: None,
+        }
+    }
+
+    #[test]
+    fn append_zscore_ignores_other_commands() {
+        let all = vec![instant_series("up", "current", 10.0)];
+        let result = append_zscore(all.clone(), &all, "compareAgainstLast28", false, 2.0);
+        assert_eq!(result, all);
+    }
+
+    #[test]
+    fn append_zscore_skips_signatures_with_fewer_than_two_historical_windows() {
+        let all = vec![
+            instant_series("up", "current", 10.0),
+            instant_series("up", "7days", 8.0),
+        ];
+        let result = append_zscore(all.clone(), &all, "zScoreAgainstLast28", false, 2.0);
+        assert_eq!(result, all);
+    }
+
+    #[test]
+    fn append_zscore_computes_zscore_and_bands_for_instant_queries() {
+        let all = vec![
+            instant_series("up", "current", 9.0),
+            instant_series("up", "7days", 4.0),
+            instant_series("up", "14days", 4.0),
+            instant_series("up", "21days", 6.0),
+        ];
+        let result = append_zscore(all.clone(), &all, "zScoreAgainstLast28", false, 2.0);
+
+        // The four original series, plus z-score + upper + lower bands.
+        assert_eq!(result.len(), all.len() + 3);
+
+        let find = |suffix: &str| {
+            result
+                .iter()
+                .find(|s| s.metric.get("chrono_timeframe").map(String::as_str) == Some(suffix))
+                .unwrap_or_else(|| panic!("missing {} series", suffix))
+        };
+
+        // historical = [4.0, 4.0, 6.0] => mean = 14/3, sample stddev ~ 1.1547
+        let z = find("zScoreAgainstLast28").value.unwrap().value;
+        assert!((z - zscore(9.0, 14.0 / 3.0, 1.1547005)).abs() < 1e-5);
+
+        let upper = find("zScoreAgainstLast28Upper").value.unwrap().value;
+        let lower = find("zScoreAgainstLast28Lower").value.unwrap().value;
+        assert!(upper > lower);
+        assert!((upper - (14.0 / 3.0 + 2.0 * 1.1547005)).abs() < 1e-5);
+    }
 }
\ No newline at end of file